@@ -2,9 +2,11 @@ use eithr::Either;
 
 use std::{
     collections::{
-        hash_map::Keys, HashMap, HashSet,
+        hash_map::{Keys, RandomState},
+        HashMap, HashSet,
     },
     fmt::{self, Write},
+    rc::Rc,
 };
 
 /// Supertrait of traits necessary to be satisfied in order to be
@@ -19,20 +21,84 @@ impl<T> Hashley for T where
 {
 }
 
-#[derive(Clone, Default)]
-pub struct Envr<K, V>(
-    HashMap<K, V>,
-    Option<Box<Self>>,
+#[derive(Clone)]
+pub struct Envr<K, V, S = RandomState>(
+    HashMap<K, V, S>,
+    Option<Rc<Self>>,
 );
 
-impl<K, V> Envr<K, V>
+impl<K, V, S> Default for Envr<K, V, S>
 where
     K: std::cmp::Eq + std::hash::Hash,
+    S: std::hash::BuildHasher + Default,
 {
-    pub fn new() -> Envr<K, V> {
+    fn default() -> Self {
+        Self(HashMap::default(), None)
+    }
+}
+
+// `new` and `with_capacity` are pinned to the default `RandomState`
+// hasher (mirroring `std::collections::HashMap`'s own split between
+// `HashMap<K, V>::new` and the hasher-generic `HashMap::with_hasher`)
+// so that they can be called without an explicit `Envr<K, V, S>`
+// annotation. Reach for `with_hasher`/`with_capacity_and_hasher` below
+// when plugging in a custom `BuildHasher`.
+impl<K, V> Envr<K, V, RandomState>
+where
+    K: std::cmp::Eq + std::hash::Hash,
+{
+    pub fn new() -> Envr<K, V, RandomState> {
         Self(HashMap::default(), None)
     }
 
+    /// Builds an empty environment whose local scope can hold at
+    /// least `capacity` entries without reallocating, using the
+    /// default hasher.
+    pub fn with_capacity(
+        capacity: usize,
+    ) -> Envr<K, V, RandomState> {
+        Self(
+            HashMap::with_capacity_and_hasher(
+                capacity,
+                RandomState::default(),
+            ),
+            None,
+        )
+    }
+}
+
+impl<K, V, S> Envr<K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash,
+    S: std::hash::BuildHasher,
+{
+    /// Builds an empty environment using the given hasher for the
+    /// local scope.
+    pub fn with_hasher(hasher: S) -> Envr<K, V, S> {
+        Self(HashMap::with_hasher(hasher), None)
+    }
+
+    /// Builds an empty environment whose local scope can hold at
+    /// least `capacity` entries without reallocating, using the
+    /// given hasher.
+    pub fn with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Envr<K, V, S> {
+        Self(
+            HashMap::with_capacity_and_hasher(
+                capacity, hasher,
+            ),
+            None,
+        )
+    }
+
+    /// Reserves capacity for at least `additional` more entries in
+    /// the local scope.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
     /// Total number of entries stored. Note that this value is equal to
     /// the number of entries stored locally + the number of entries
     /// inherited
@@ -45,21 +111,44 @@ where
     }
 
     pub fn new_from(
-        parent: Option<Envr<K, V>>,
-    ) -> Envr<K, V> {
-        Self(HashMap::new(), parent.map(Box::new))
+        parent: Option<Envr<K, V, S>>,
+    ) -> Envr<K, V, S>
+    where
+        S: Default + Clone,
+    {
+        let local = match &parent {
+            Some(p) => {
+                HashMap::with_hasher(p.0.hasher().clone())
+            }
+            None => HashMap::default(),
+        };
+        Self(local, parent.map(Rc::new))
     }
 
-    pub fn get_locals(&self) -> &HashMap<K, V> {
+    pub fn get_locals(&self) -> &HashMap<K, V, S> {
         &self.0
     }
 
     pub fn get_parent(
         &self,
-    ) -> &Option<Box<Envr<K, V>>> {
+    ) -> &Option<Rc<Envr<K, V, S>>> {
         &self.1
     }
 
+    /// Returns a mutable reference to the parent environment,
+    /// cloning its contents first if it is shared with other
+    /// environments (copy-on-write).
+    pub fn get_parent_mut(
+        &mut self,
+    ) -> Option<&mut Envr<K, V, S>>
+    where
+        K: Clone,
+        V: Clone,
+        S: Clone,
+    {
+        self.1.as_mut().map(Rc::make_mut)
+    }
+
     /// Returns `true` if the environment has a parent environment, i.e.,
     /// if it is the extension of another environment.
     /// Otherwise returns `false`
@@ -67,32 +156,102 @@ where
         self.1.is_some()
     }
 
-    pub fn extend(self) -> Envr<K, V> {
-        Self(HashMap::new(), Some(Box::new(self)))
+    pub fn extend(self) -> Envr<K, V, S>
+    where
+        S: Clone,
+    {
+        let local = HashMap::with_hasher(self.0.hasher().clone());
+        Self(local, Some(Rc::new(self)))
     }
 
     /// Clones the environment and produces a new environment
-    /// extended from the clone.
-    pub fn extension(&self) -> Envr<K, V>
+    /// extended from the clone. Since the parent link is an `Rc`,
+    /// this only deep-clones the local scope being extended from;
+    /// the ancestor chain it shares is a cheap pointer clone.
+    pub fn extension(&self) -> Envr<K, V, S>
     where
         K: Clone,
         V: Clone,
+        S: Clone,
     {
+        let local = HashMap::with_hasher(self.0.hasher().clone());
         Self(
-            HashMap::new(),
-            Some(Box::new(self.clone())),
+            local,
+            Some(Rc::new(self.clone())),
         )
     }
 
+    /// Pushes a fresh, empty local scope in place, moving the
+    /// environment's current contents down to become its parent.
+    /// The in-place analogue of [`Envr::extend`] — pair with
+    /// [`Envr::pop_scope`] or [`Envr::scope`] for exception-safe
+    /// block scoping without threading a new value back up.
+    pub fn push_scope(&mut self)
+    where
+        S: Default + Clone,
+    {
+        let old = std::mem::take(self);
+        let local = HashMap::with_hasher(old.0.hasher().clone());
+        *self = Self(local, Some(Rc::new(old)));
+    }
+
+    /// Discards the innermost local scope, restoring the parent
+    /// environment in place and returning the discarded scope's
+    /// bindings. Returns `None`, leaving `self` untouched, if there
+    /// is no parent to restore.
+    pub fn pop_scope(&mut self) -> Option<HashMap<K, V, S>>
+    where
+        K: Clone,
+        V: Clone,
+        S: Default + Clone,
+    {
+        let current = std::mem::take(self);
+        match current.1 {
+            Some(parent) => {
+                *self = Rc::try_unwrap(parent)
+                    .unwrap_or_else(|shared| {
+                        (*shared).clone()
+                    });
+                Some(current.0)
+            }
+            None => {
+                *self = current;
+                None
+            }
+        }
+    }
+
+    /// Enters a block scope, returning an RAII guard that pops it
+    /// again on `Drop`. Lets callers write
+    /// `{ let mut _g = env.scope(); ... }` for exception-safe
+    /// lexical scoping.
+    pub fn scope(&mut self) -> ScopeGuard<'_, K, V, S>
+    where
+        K: Clone,
+        V: Clone,
+        S: Default + Clone,
+    {
+        self.push_scope();
+        ScopeGuard { env: self }
+    }
+
     /// Searches for a key only in the local (=first) field.
     /// Does not search in ancestor (=second)
-    pub fn contains_local(&self, k: &K) -> bool {
+    pub fn contains_local<Q>(&self, k: &Q) -> bool
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: std::hash::Hash + Eq + ?Sized,
+    {
         self.0.contains_key(k)
     }
 
     /// Searches for a key in both fields. If a key is not locally bound,
     /// then ancestor environments are searched.
-    pub fn contains(&self, k: &K) -> bool {
+    pub fn contains<Q>(&self, k: &Q) -> bool
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: std::hash::Hash + Eq + ?Sized,
+    {
         let mut tmp = self;
         loop {
             if tmp.0.contains_key(k) {
@@ -138,7 +297,11 @@ where
     }
 
     /// Get a reference to the value stored for a certain key.
-    pub fn get(&self, k: &K) -> Option<&V> {
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: std::borrow::Borrow<Q>,
+        Q: std::hash::Hash + Eq + ?Sized,
+    {
         if let Some(v) = self.0.get(k) {
             Some(v)
         } else {
@@ -151,32 +314,45 @@ where
     }
 
     /// Get a mutable reference to the value stored for a provided key.
-    pub fn get_mut(
+    /// Mirrors [`Envr::get`] in searching ancestor scopes, cloning an
+    /// ancestor scope first if it is shared with other environments
+    /// (the same copy-on-write behavior as [`Envr::get_parent_mut`]),
+    /// so that a key reported as bound by [`Envr::contains`] can
+    /// always be reached here too.
+    pub fn get_mut<Q>(
         &mut self,
-        k: &K,
-    ) -> Option<&mut V> {
-        if let Some(v) = self.0.get_mut(k) {
-            Some(v)
+        k: &Q,
+    ) -> Option<&mut V>
+    where
+        K: std::borrow::Borrow<Q> + Clone,
+        V: Clone,
+        S: Clone,
+        Q: std::hash::Hash + Eq + ?Sized,
+    {
+        if self.0.contains_key(k) {
+            self.0.get_mut(k)
         } else {
-            if let Some(ref mut p) = self.1 {
-                p.get_mut(k)
-            } else {
-                None
-            }
+            self.get_parent_mut().and_then(|p| p.get_mut(k))
         }
     }
 
-    /// Update the value of an entry matching a given key.
-    /// If the key exists, the value is updated and a  
-    /// reference to the newly inserted value is returned
-    /// as an `Either::Left`.
-    /// If the key doesn't exist, the provided value is returned
-    /// as an `Either::Right`.
-    pub fn update(
+    /// Update the value of a bound entry matching a given key,
+    /// searching ancestor scopes the same way [`Envr::get_mut`] does.
+    /// If the key is bound, the value is updated and a reference to
+    /// the newly inserted value is returned as an `Either::Left`. If
+    /// the key is unbound, the provided value is returned as an
+    /// `Either::Right`.
+    pub fn update<Q>(
         &mut self,
-        k: &K,
+        k: &Q,
         v: V,
-    ) -> Either<&V, V> {
+    ) -> Either<&V, V>
+    where
+        K: std::borrow::Borrow<Q> + Clone,
+        V: Clone,
+        S: Clone,
+        Q: std::hash::Hash + Eq + ?Sized,
+    {
         if let Some(v0) = self.get_mut(k) {
             *v0 = v;
             // safe to unwrap, since we know it exists
@@ -189,15 +365,29 @@ where
     /// Flattens the structure into a single environment with no
     /// parent. Shadowed bindings are overwritten in order of traversal,
     /// therefore newer bindings take precedence over older ones.
-    pub fn flatten(self) -> Envr<K, V> {
-        let mut env = Envr::new_from(None);
+    pub fn flatten(self) -> Envr<K, V, S>
+    where
+        K: Clone,
+        V: Clone,
+        S: Default + Clone,
+    {
+        let mut env = Self(
+            HashMap::with_hasher(self.0.hasher().clone()),
+            None,
+        );
         let mut tmp = self;
         loop {
             for (k, v) in tmp.0 {
-                env.0.insert(k, v);
+                // Keep the innermost value for a shadowed key, same
+                // as `iter`'s precedence: the first (closest) scope
+                // to claim a key wins.
+                env.0.entry(k).or_insert(v);
             }
             if let Some(p) = tmp.1 {
-                tmp = *p;
+                tmp = Rc::try_unwrap(p)
+                    .unwrap_or_else(|shared| {
+                        (*shared).clone()
+                    });
             } else {
                 break;
             }
@@ -205,6 +395,18 @@ where
         env
     }
 
+    /// Returns an entry for the given key in the local scope,
+    /// mirroring `std::collections::HashMap::entry`. Use
+    /// [`Entry::or_insert_inherited`] for an inheritance-aware
+    /// insertion that consults ancestor scopes before falling back
+    /// to a default.
+    pub fn entry(&mut self, k: K) -> Entry<'_, K, V, S> {
+        Entry {
+            parent: &self.1,
+            inner: self.0.entry(k),
+        }
+    }
+
     pub fn keylist(&self) -> Vec<Keys<K, V>> {
         let mut keylist = vec![];
         let mut tmp = self;
@@ -235,11 +437,63 @@ where
         set
     }
 
+    /// Iterates over the *effective* environment: each visible key
+    /// exactly once, bound to its nearest (innermost) value. Walks
+    /// from the local scope outward, skipping ancestor bindings
+    /// already shadowed by a closer scope. Unlike [`Envr::flatten`],
+    /// this does not clone keys or values — only a `HashSet` of seen
+    /// keys and a `Vec` of borrowed entries are allocated.
+    pub fn iter(&self) -> std::vec::IntoIter<(&K, &V)> {
+        let mut seen = HashSet::new();
+        let mut items = Vec::new();
+        let mut tmp = self;
+        loop {
+            for (k, v) in &tmp.0 {
+                if seen.insert(k) {
+                    items.push((k, v));
+                }
+            }
+            if let Some(p) = &tmp.1 {
+                tmp = p.as_ref();
+            } else {
+                break;
+            }
+        }
+        items.into_iter()
+    }
+
+    /// Mutable counterpart to [`Envr::iter`]. Ancestor scopes shared
+    /// with other environments are made unique via copy-on-write
+    /// before being yielded.
+    pub fn iter_mut(&mut self) -> std::vec::IntoIter<(&K, &mut V)>
+    where
+        K: Clone,
+        V: Clone,
+        S: Clone,
+    {
+        let mut seen = HashSet::new();
+        let mut items = Vec::new();
+        let mut tmp = self;
+        loop {
+            let cur = tmp;
+            for (k, v) in cur.0.iter_mut() {
+                if seen.insert(k) {
+                    items.push((k, v));
+                }
+            }
+            match cur.1.as_mut().map(Rc::make_mut) {
+                Some(parent) => tmp = parent,
+                None => break,
+            }
+        }
+        items.into_iter()
+    }
+
     pub fn difference<'t>(
         &'t self,
-        other: &'t Envr<K, V>,
+        other: &'t Envr<K, V, S>,
     ) -> Envr<&'t K, &'t V> {
-        let mut env = Envr::new_from(None);
+        let mut env = Envr::new();
         let this = self.keyset();
         let that = other.keyset();
         let keys = this.difference(&that);
@@ -274,24 +528,199 @@ where
     }
 }
 
-impl<K, V> PartialEq for Envr<K, V>
+/// A view into a single local-scope entry, returned by
+/// [`Envr::entry`]. Mirrors `std::collections::hash_map::Entry`, with
+/// an additional inheritance-aware insertion method.
+pub struct Entry<'a, K, V, S> {
+    parent: &'a Option<Rc<Envr<K, V, S>>>,
+    inner: std::collections::hash_map::Entry<'a, K, V>,
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash,
+    S: std::hash::BuildHasher,
+{
+    /// Returns a reference to the entry's key.
+    pub fn key(&self) -> &K {
+        self.inner.key()
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if
+    /// empty, then returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.inner.or_insert(default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of
+    /// `default` if empty, then returns a mutable reference to the
+    /// value.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        self.inner.or_insert_with(default)
+    }
+
+    /// Provides in-place mutable access to an occupied entry before
+    /// any potential inserts.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        Self {
+            parent: self.parent,
+            inner: self.inner.and_modify(f),
+        }
+    }
+
+    /// Ensures a value is bound for this key in the local scope. If
+    /// the local slot is vacant, ancestor scopes are searched first:
+    /// a value found there is cloned into the local scope, and only
+    /// a key that is unbound anywhere in the chain falls back to
+    /// `default`.
+    pub fn or_insert_inherited(self, default: V) -> &'a mut V
+    where
+        V: Clone,
+    {
+        use std::collections::hash_map::Entry as StdEntry;
+        match self.inner {
+            StdEntry::Occupied(e) => e.into_mut(),
+            StdEntry::Vacant(e) => {
+                let inherited = self
+                    .parent
+                    .as_ref()
+                    .and_then(|p| p.get(e.key()))
+                    .cloned();
+                match inherited {
+                    Some(v) => e.insert(v),
+                    None => e.insert(default),
+                }
+            }
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a Envr<K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash,
+    S: std::hash::BuildHasher,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = std::vec::IntoIter<(&'a K, &'a V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Consumes the chain, yielding each visible key exactly once bound
+/// to its nearest (innermost) value.
+impl<K, V, S> IntoIterator for Envr<K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash + Clone,
+    V: Clone,
+    S: std::hash::BuildHasher + Clone,
+{
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut seen = HashSet::new();
+        let mut items = Vec::new();
+        let mut tmp = self;
+        loop {
+            for (k, v) in tmp.0 {
+                if seen.insert(k.clone()) {
+                    items.push((k, v));
+                }
+            }
+            if let Some(p) = tmp.1 {
+                tmp = Rc::try_unwrap(p)
+                    .unwrap_or_else(|shared| {
+                        (*shared).clone()
+                    });
+            } else {
+                break;
+            }
+        }
+        items.into_iter()
+    }
+}
+
+/// RAII guard returned by [`Envr::scope`]. Holds a pushed local
+/// scope open and pops it again on `Drop`, restoring the parent
+/// environment in place even if the scope is exited via an early
+/// return or a panic.
+pub struct ScopeGuard<'a, K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash + Clone,
+    V: Clone,
+    S: std::hash::BuildHasher + Default + Clone,
+{
+    env: &'a mut Envr<K, V, S>,
+}
+
+impl<'a, K, V, S> std::ops::Deref for ScopeGuard<'a, K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash + Clone,
+    V: Clone,
+    S: std::hash::BuildHasher + Default + Clone,
+{
+    type Target = Envr<K, V, S>;
+
+    fn deref(&self) -> &Self::Target {
+        self.env
+    }
+}
+
+impl<'a, K, V, S> std::ops::DerefMut for ScopeGuard<'a, K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash + Clone,
+    V: Clone,
+    S: std::hash::BuildHasher + Default + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.env
+    }
+}
+
+impl<'a, K, V, S> Drop for ScopeGuard<'a, K, V, S>
+where
+    K: std::cmp::Eq + std::hash::Hash + Clone,
+    V: Clone,
+    S: std::hash::BuildHasher + Default + Clone,
+{
+    fn drop(&mut self) {
+        self.env.pop_scope();
+    }
+}
+
+impl<K, V, S> PartialEq for Envr<K, V, S>
 where
     K: Hashley,
     V: Hashley,
+    S: std::hash::BuildHasher,
 {
     fn eq(&self, other: &Self) -> bool {
         self.0.eq(&other.0) && self.1 == other.1
     }
 }
 
-impl<K, V, I> From<I> for Envr<K, V>
+// Bounded to array literals with the default hasher (rather than a
+// blanket `I: IntoIterator`, mirroring how `std` implements
+// `From<[(K, V); N]>` only for `HashMap<K, V, RandomState>`) since
+// `Envr` now implements `IntoIterator` itself: a fully generic
+// `impl<I> From<I>` would overlap with the standard library's
+// reflexive `impl<T> From<T> for T` once `I` can be `Envr` itself.
+impl<K, V, const N: usize> From<[(K, V); N]>
+    for Envr<K, V, RandomState>
 where
-    I: IntoIterator<Item = (K, V)>,
     K: Hashley,
     V: Hashley,
 {
-    fn from(iter: I) -> Self {
-        let mut env = Envr::new_from(None);
+    fn from(iter: [(K, V); N]) -> Self {
+        let mut env = Envr::new();
         for (k, v) in iter {
             env.0.insert(k, v);
         }
@@ -299,7 +728,28 @@ where
     }
 }
 
-impl<K, V> std::fmt::Debug for Envr<K, V>
+// Mirrors `std`'s `HashMap<K, V, S>: FromIterator<(K, V)>` — unlike
+// `From`, `FromIterator` doesn't collide with the reflexive `From<T>
+// for T`, so this covers `Vec`s, other `HashMap`s, or any other
+// iterable of pairs, restoring the general-iterable construction
+// that narrowing `From` to array literals gave up.
+impl<K, V, S> std::iter::FromIterator<(K, V)> for Envr<K, V, S>
+where
+    K: Hashley,
+    S: std::hash::BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(
+        iter: I,
+    ) -> Self {
+        let mut env = Self::with_hasher(S::default());
+        for (k, v) in iter {
+            env.0.insert(k, v);
+        }
+        env
+    }
+}
+
+impl<K, V, S> std::fmt::Debug for Envr<K, V, S>
 where
     K: std::fmt::Debug,
     V: std::fmt::Debug,
@@ -322,7 +772,7 @@ where
     }
 }
 
-impl<K, V> std::fmt::Display for Envr<K, V>
+impl<K, V, S> std::fmt::Display for Envr<K, V, S>
 where
     K: std::fmt::Display,
     V: std::fmt::Display,
@@ -359,6 +809,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::Envr;
+    use eithr::Either;
 
     #[test]
     fn it_works() {
@@ -377,6 +828,10 @@ mod tests {
         println!("{}", &env);
         let env = env.flatten();
         println!("{}", &env);
+        // `4` is shadowed (outer "d", inner "f"); flatten keeps the
+        // innermost value.
+        assert_eq!(env.get(&4), Some(&"f"));
+        assert_eq!(env.get(&5), Some(&"e"));
     }
 
     #[test]
@@ -401,4 +856,81 @@ mod tests {
             Envr::from([(&"c", &10)])
         )
     }
+
+    #[test]
+    fn entry_and_iter() {
+        let mut env = Envr::from([
+            (1, "a"),
+            (2, "b"),
+        ]);
+        env.entry(3).or_insert("c");
+        assert_eq!(env.get(&3), Some(&"c"));
+
+        let mut env = env.extend();
+        assert_eq!(
+            env.entry(1).or_insert_inherited("z"),
+            &mut "a"
+        );
+        assert_eq!(env.get_locals().get(&1), Some(&"a"));
+
+        let mut seen: Vec<_> = env.iter().collect();
+        seen.sort();
+        assert_eq!(
+            seen,
+            vec![(&1, &"a"), (&2, &"b"), (&3, &"c")]
+        );
+    }
+
+    #[test]
+    fn scope_guard_restores_parent() {
+        let mut env = Envr::from([(1, "a")]);
+        {
+            let mut inner = env.scope();
+            inner.set(2, "b");
+            assert_eq!(inner.get(&1), Some(&"a"));
+            assert_eq!(inner.get(&2), Some(&"b"));
+        }
+        assert_eq!(env.get(&1), Some(&"a"));
+        assert_eq!(env.get(&2), None);
+    }
+
+    #[test]
+    fn update_reaches_ancestor_scope() {
+        let mut child = Envr::from([(1, "a")]).extend();
+        assert!(child.contains(&1));
+        assert_eq!(
+            child.update(&1, "z"),
+            Either::Left(&"z")
+        );
+        assert_eq!(child.get(&1), Some(&"z"));
+        assert_eq!(
+            child.get_parent().as_ref().unwrap().get(&1),
+            Some(&"z")
+        );
+    }
+
+    #[test]
+    fn from_iter_builds_from_a_vec() {
+        let env: Envr<i32, &str> =
+            vec![(1, "a"), (2, "b")]
+                .into_iter()
+                .collect();
+        assert_eq!(env.get(&1), Some(&"a"));
+        assert_eq!(env.get(&2), Some(&"b"));
+    }
+
+    #[test]
+    fn flatten_and_iter_agree_on_shadowing() {
+        let mut env = Envr::from([(1, "outer")])
+            .extend()
+            .extend();
+        env.set(1, "inner");
+
+        let mut seen: Vec<_> = env.iter().collect();
+        seen.sort();
+        assert_eq!(seen, vec![(&1, &"inner")]);
+
+        let flat = env.flatten();
+        assert_eq!(flat.get(&1), Some(&"inner"));
+    }
 }